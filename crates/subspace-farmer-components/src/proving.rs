@@ -5,6 +5,7 @@ use crate::sector::{
 };
 use crate::{ReadAt, ReadAtSync};
 use futures::FutureExt;
+use rayon::prelude::*;
 use std::collections::VecDeque;
 use std::io;
 use subspace_core_primitives::crypto::kzg::Kzg;
@@ -158,10 +159,143 @@ where
             table_generator,
         )
     }
+
+    /// Turn solution candidates into actual solutions, proving every winning chunk in parallel on a
+    /// rayon thread pool instead of one at a time.
+    ///
+    /// The per-[`WinningChunk`] work (PoSpace table derivation, record chunk reads, erasure
+    /// decoding and KZG witness creation) is distributed across cores while the resulting solutions
+    /// keep their original ordering by `solution_distance` and the same
+    /// [`ProvableSolutions`]/[`ExactSizeIterator`] contract as [`into_solutions`]. This is a pure
+    /// throughput win for sectors with many winning candidates that must be proven within a tight
+    /// slot deadline.
+    ///
+    /// [`into_solutions`]: Self::into_solutions
+    pub fn into_solutions_parallel<RewardAddress, PosTable, TableGenerator>(
+        self,
+        reward_address: &'a RewardAddress,
+        kzg: &'a Kzg,
+        erasure_coding: &'a ErasureCoding,
+        table_generator: TableGenerator,
+    ) -> Result<impl ProvableSolutions<Item = MaybeSolution<RewardAddress>> + 'a, ProvingError>
+    where
+        RewardAddress: Copy + Send + Sync,
+        PosTable: Table,
+        TableGenerator: (Fn(&PosSeed) -> PosTable) + Sync + 'a,
+        Sector: Sync,
+    {
+        let solutions_iterator = SolutionsIterator::<'a, _, PosTable, _, _>::new(
+            self.public_key,
+            reward_address,
+            self.sector_id,
+            self.s_bucket,
+            self.sector,
+            self.sector_metadata,
+            kzg,
+            erasure_coding,
+            self.chunk_candidates,
+            table_generator,
+        )?;
+
+        Ok(solutions_iterator.into_parallel())
+    }
 }
 
 type MaybeSolution<RewardAddress> = Result<Solution<PublicKey, RewardAddress>, ProvingError>;
 
+/// Prove a single winning chunk into a solution. Shared by the sequential [`SolutionsIterator`] and
+/// the parallel proving path so both produce identical solutions; the only difference is where the
+/// work runs.
+#[allow(clippy::too_many_arguments)]
+fn prove_winning_chunk<RewardAddress, PosTable, Sector>(
+    public_key: &PublicKey,
+    reward_address: &RewardAddress,
+    s_bucket: SBucket,
+    sector_metadata: &SectorMetadataChecksummed,
+    s_bucket_offsets: &[u32; Record::NUM_S_BUCKETS],
+    kzg: &Kzg,
+    erasure_coding: &ErasureCoding,
+    sector_contents_map: &SectorContentsMap,
+    sector: &ReadAt<Sector, !>,
+    pos_table: &PosTable,
+    winning_chunk: &WinningChunk,
+) -> MaybeSolution<RewardAddress>
+where
+    RewardAddress: Copy,
+    PosTable: Table,
+    Sector: ReadAtSync,
+{
+    let WinningChunk {
+        chunk_offset,
+        piece_offset,
+        solution_distance: _,
+    } = *winning_chunk;
+
+    try {
+        let sector_record_chunks_fut = read_sector_record_chunks(
+            piece_offset,
+            sector_metadata.pieces_in_sector,
+            s_bucket_offsets,
+            sector_contents_map,
+            pos_table,
+            sector,
+        );
+        let sector_record_chunks = sector_record_chunks_fut
+            .now_or_never()
+            .expect("Sync reader; qed")?;
+
+        let chunk = sector_record_chunks
+            .get(usize::from(s_bucket))
+            .expect("Within s-bucket range; qed")
+            .expect("Winning chunk was plotted; qed");
+
+        let source_chunks_polynomial = erasure_coding
+            .recover_poly(sector_record_chunks.as_slice())
+            .map_err(|error| ReadingError::FailedToErasureDecodeRecord {
+                piece_offset,
+                error,
+            })?;
+        drop(sector_record_chunks);
+
+        // NOTE: We do not check plot consistency using checksum because it is more
+        // expensive and consensus will verify validity of the proof anyway
+        let record_metadata_fut =
+            read_record_metadata(piece_offset, sector_metadata.pieces_in_sector, sector);
+        let record_metadata = record_metadata_fut
+            .now_or_never()
+            .expect("Sync reader; qed")?;
+
+        let proof_of_space = pos_table.find_proof(s_bucket.into()).expect(
+            "Quality exists for this s-bucket, otherwise it wouldn't be a winning chunk; qed",
+        );
+
+        let chunk_witness = kzg
+            .create_witness(
+                &source_chunks_polynomial,
+                Record::NUM_S_BUCKETS,
+                s_bucket.into(),
+            )
+            .map_err(|error| ProvingError::FailedToCreateChunkWitness {
+                piece_offset,
+                chunk_offset,
+                error,
+            })?;
+
+        Solution {
+            public_key: *public_key,
+            reward_address: *reward_address,
+            sector_index: sector_metadata.sector_index,
+            history_size: sector_metadata.history_size,
+            piece_offset,
+            record_commitment: record_metadata.commitment,
+            record_witness: record_metadata.witness,
+            chunk,
+            chunk_witness: ChunkWitness::from(chunk_witness),
+            proof_of_space,
+        }
+    }
+}
+
 struct SolutionsIterator<'a, RewardAddress, PosTable, TableGenerator, Sector>
 where
     Sector: ReadAtSync + 'a,
@@ -205,94 +339,31 @@ where
     type Item = MaybeSolution<RewardAddress>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let WinningChunk {
-            chunk_offset,
-            piece_offset,
-            solution_distance: _,
-        } = self.winning_chunks.pop_front()?;
+        let winning_chunk = self.winning_chunks.pop_front()?;
 
         self.count -= 1;
 
         // Derive PoSpace table
         let pos_table = (self.table_generator)(
-            &self
-                .sector_id
-                .derive_evaluation_seed(piece_offset, self.sector_metadata.history_size),
+            &self.sector_id.derive_evaluation_seed(
+                winning_chunk.piece_offset,
+                self.sector_metadata.history_size,
+            ),
         );
 
-        let maybe_solution: Result<_, ProvingError> = try {
-            let sector_record_chunks_fut = read_sector_record_chunks(
-                piece_offset,
-                self.sector_metadata.pieces_in_sector,
-                &self.s_bucket_offsets,
-                &self.sector_contents_map,
-                &pos_table,
-                &self.sector,
-            );
-            let sector_record_chunks = sector_record_chunks_fut
-                .now_or_never()
-                .expect("Sync reader; qed")?;
-
-            let chunk = sector_record_chunks
-                .get(usize::from(self.s_bucket))
-                .expect("Within s-bucket range; qed")
-                .expect("Winning chunk was plotted; qed");
-
-            let source_chunks_polynomial = self
-                .erasure_coding
-                .recover_poly(sector_record_chunks.as_slice())
-                .map_err(|error| ReadingError::FailedToErasureDecodeRecord {
-                    piece_offset,
-                    error,
-                })?;
-            drop(sector_record_chunks);
-
-            // NOTE: We do not check plot consistency using checksum because it is more
-            // expensive and consensus will verify validity of the proof anyway
-            let record_metadata_fut = read_record_metadata(
-                piece_offset,
-                self.sector_metadata.pieces_in_sector,
-                &self.sector,
-            );
-            let record_metadata = record_metadata_fut
-                .now_or_never()
-                .expect("Sync reader; qed")?;
-
-            let proof_of_space = pos_table.find_proof(self.s_bucket.into()).expect(
-                "Quality exists for this s-bucket, otherwise it wouldn't be a winning chunk; qed",
-            );
-
-            let chunk_witness = self
-                .kzg
-                .create_witness(
-                    &source_chunks_polynomial,
-                    Record::NUM_S_BUCKETS,
-                    self.s_bucket.into(),
-                )
-                .map_err(|error| ProvingError::FailedToCreateChunkWitness {
-                    piece_offset,
-                    chunk_offset,
-                    error,
-                })?;
-
-            Solution {
-                public_key: *self.public_key,
-                reward_address: *self.reward_address,
-                sector_index: self.sector_metadata.sector_index,
-                history_size: self.sector_metadata.history_size,
-                piece_offset,
-                record_commitment: record_metadata.commitment,
-                record_witness: record_metadata.witness,
-                chunk,
-                chunk_witness: ChunkWitness::from(chunk_witness),
-                proof_of_space,
-            }
-        };
-
-        match maybe_solution {
-            Ok(solution) => Some(Ok(solution)),
-            Err(error) => Some(Err(error)),
-        }
+        Some(prove_winning_chunk(
+            self.public_key,
+            self.reward_address,
+            self.s_bucket,
+            self.sector_metadata,
+            &self.s_bucket_offsets,
+            self.kzg,
+            self.erasure_coding,
+            &self.sector_contents_map,
+            &self.sector,
+            &pos_table,
+            &winning_chunk,
+        ))
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
@@ -394,4 +465,96 @@ where
             table_generator,
         })
     }
+
+    /// Prove all remaining winning chunks on a rayon thread pool and collect the solutions into an
+    /// eagerly-computed iterator, preserving their `solution_distance` ordering.
+    fn into_parallel(self) -> PrecomputedSolutions<RewardAddress>
+    where
+        RewardAddress: Send + Sync,
+        TableGenerator: Fn(&PosSeed) -> PosTable + Sync,
+        Sector: Sync,
+    {
+        let Self {
+            public_key,
+            reward_address,
+            sector_id,
+            s_bucket,
+            sector_metadata,
+            s_bucket_offsets,
+            kzg,
+            erasure_coding,
+            sector_contents_map,
+            sector,
+            winning_chunks,
+            best_solution_distance,
+            table_generator,
+            ..
+        } = self;
+
+        let winning_chunks = Vec::from(winning_chunks);
+        let remaining = winning_chunks.len();
+
+        // `into_par_iter().collect()` preserves input order, keeping the `solution_distance`
+        // ordering established while building `winning_chunks`.
+        let solutions = winning_chunks
+            .into_par_iter()
+            .map(|winning_chunk| {
+                let pos_table = table_generator(&sector_id.derive_evaluation_seed(
+                    winning_chunk.piece_offset,
+                    sector_metadata.history_size,
+                ));
+
+                prove_winning_chunk(
+                    public_key,
+                    reward_address,
+                    s_bucket,
+                    sector_metadata,
+                    &s_bucket_offsets,
+                    kzg,
+                    erasure_coding,
+                    &sector_contents_map,
+                    &sector,
+                    &pos_table,
+                    &winning_chunk,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        PrecomputedSolutions {
+            solutions: solutions.into_iter(),
+            remaining,
+            best_solution_distance,
+        }
+    }
+}
+
+/// Eagerly-computed solutions produced by the parallel proving path. Holds the proven solutions in
+/// their original order and hands them out one at a time, satisfying the same
+/// [`ProvableSolutions`]/[`ExactSizeIterator`] contract as [`SolutionsIterator`].
+struct PrecomputedSolutions<RewardAddress> {
+    solutions: std::vec::IntoIter<MaybeSolution<RewardAddress>>,
+    remaining: usize,
+    best_solution_distance: Option<SolutionRange>,
+}
+
+impl<RewardAddress> Iterator for PrecomputedSolutions<RewardAddress> {
+    type Item = MaybeSolution<RewardAddress>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let solution = self.solutions.next()?;
+        self.remaining -= 1;
+        Some(solution)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<RewardAddress> ExactSizeIterator for PrecomputedSolutions<RewardAddress> {}
+
+impl<RewardAddress> ProvableSolutions for PrecomputedSolutions<RewardAddress> {
+    fn best_solution_distance(&self) -> Option<SolutionRange> {
+        self.best_solution_distance
+    }
 }
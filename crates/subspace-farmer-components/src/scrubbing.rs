@@ -0,0 +1,280 @@
+//! Self-healing sector scrub that erasure-reconstructs and rewrites corrupt record chunks.
+//!
+//! The proving path deliberately skips checksum validation ("consensus will verify validity
+//! anyway"), so silently bit-rotted sectors are only discovered when a solution is rejected. This
+//! module walks a plotted sector and, for each record, erasure-decodes the stored chunks and
+//! recomputes the record commitment, comparing it against the commitment stored in the record
+//! metadata — the integrity check the reading path omits. A mismatch means at least one *present*
+//! shard is wrong (a silent bit-flip), not merely missing: [`ErasureCoding`] fills missing shards
+//! (erasures) for free, but correcting an *error* first requires locating the bad shard, which
+//! costs one unit of redundancy. The scrub therefore locates the corrupt shard by dropping one
+//! surviving shard at a time until the reconstruction's commitment matches the metadata, rebuilds
+//! the record from the remainder, and rewrites it through the same layout code the reading path
+//! uses in reverse — then re-reads and re-verifies, only reporting success once the commitment is
+//! actually restored. The returned [`ScrubReport`] lists which piece offsets were repaired and which were too
+//! damaged to recover, turning detected corruption into automatic recovery instead of forcing a
+//! full replot of the affected sector.
+
+use crate::reading::{
+    read_record_metadata, read_sector_record_chunks, write_sector_record_chunks, ReadingError,
+    RecordMetadata,
+};
+use crate::sector::{
+    SectorContentsMap, SectorContentsMapFromBytesError, SectorMetadataChecksummed,
+};
+use crate::{ReadAt, ReadAtSync, WriteAtSync};
+use futures::FutureExt;
+use std::io;
+use subspace_core_primitives::crypto::kzg::{Kzg, Scalar};
+use subspace_core_primitives::{PieceOffset, PosSeed, Record, RecordCommitment, SectorId};
+use subspace_erasure_coding::ErasureCoding;
+use subspace_proof_of_space::Table;
+use thiserror::Error;
+use tracing::{debug, trace, warn};
+
+/// Errors that happen while scrubbing a sector.
+#[derive(Debug, Error)]
+pub enum ScrubbingError {
+    /// Invalid erasure coding instance
+    #[error("Invalid erasure coding instance")]
+    InvalidErasureCodingInstance,
+    /// Failed to decode sector contents map
+    #[error("Failed to decode sector contents map: {0}")]
+    FailedToDecodeSectorContentsMap(#[from] SectorContentsMapFromBytesError),
+    /// Failed to erasure-decode record
+    #[error("Failed to erasure-decode record at offset {piece_offset}: {error}")]
+    FailedToErasureDecodeRecord {
+        /// Piece offset
+        piece_offset: PieceOffset,
+        /// Lower-level error
+        error: String,
+    },
+    /// I/O error occurred
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    /// Record reading error
+    #[error("Record reading error: {0}")]
+    RecordReadingError(#[from] ReadingError),
+}
+
+/// Outcome of scrubbing a single sector.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ScrubReport {
+    /// Piece offsets whose corrupt chunks were erasure-reconstructed and rewritten in place
+    pub repaired: Vec<PieceOffset>,
+    /// Piece offsets that were corrupt and could not be repaired: no single dropped shard restored
+    /// the record commitment, or the rewritten chunks still did not match it
+    pub unrecoverable: Vec<PieceOffset>,
+}
+
+impl ScrubReport {
+    /// Returns `true` if the sector was fully healthy or fully repaired.
+    pub fn is_healthy(&self) -> bool {
+        self.unrecoverable.is_empty()
+    }
+}
+
+/// Scrub a single plotted sector, erasure-reconstructing and rewriting any corrupt record chunks
+/// that can be localized and reconstructed, and reporting the ones that cannot.
+///
+/// Mirrors the reading/proving access pattern: the sector is read through [`ReadAt`] while repaired
+/// chunks are written back through [`write_sector_record_chunks`], the exact inverse of the reader,
+/// so scrub never reimplements on-disk addressing or PoSpace masking. Corruption is detected by
+/// re-deriving the record commitment from the stored chunks (via the same KZG machinery the proving
+/// path uses) and comparing it against the commitment in the record metadata; unlike the reading
+/// path, which performs no validation, this actually catches silent bit rot.
+///
+/// A commitment mismatch means a *present* shard is wrong rather than missing, so recovery requires
+/// locating it before reconstructing — which consumes one shard of redundancy. A record is therefore
+/// repairable only when strictly more than [`Record::NUM_CHUNKS`] of its [`Record::NUM_S_BUCKETS`]
+/// shards survive; a record storing exactly [`Record::NUM_CHUNKS`] shards has none to spare and is
+/// reported unrecoverable.
+pub fn scrub_sector<PosTable, Sector, TableGenerator>(
+    sector: &Sector,
+    sector_id: SectorId,
+    sector_metadata: &SectorMetadataChecksummed,
+    kzg: &Kzg,
+    erasure_coding: &ErasureCoding,
+    mut table_generator: TableGenerator,
+) -> Result<ScrubReport, ScrubbingError>
+where
+    PosTable: Table,
+    Sector: WriteAtSync,
+    for<'r> &'r Sector: ReadAtSync,
+    TableGenerator: FnMut(&PosSeed) -> PosTable,
+{
+    if erasure_coding.max_shards() < Record::NUM_S_BUCKETS {
+        return Err(ScrubbingError::InvalidErasureCodingInstance);
+    }
+
+    let pieces_in_sector = sector_metadata.pieces_in_sector;
+    let s_bucket_offsets = sector_metadata.s_bucket_offsets();
+    let sector_contents_map_size = SectorContentsMap::encoded_size(pieces_in_sector);
+
+    let sector_contents_map = {
+        let mut sector_contents_map_bytes = vec![0; sector_contents_map_size];
+        sector.read_at(&mut sector_contents_map_bytes, 0)?;
+        SectorContentsMap::from_bytes(&sector_contents_map_bytes, pieces_in_sector)?
+    };
+
+    let sector_read = ReadAt::from_sync(sector);
+
+    let mut report = ScrubReport::default();
+
+    for piece_offset in (0..pieces_in_sector).map(PieceOffset::from) {
+        // Derive PoSpace table so reading can un-mask the chunks exactly as proving does.
+        let pos_table = table_generator(
+            &sector_id.derive_evaluation_seed(piece_offset, sector_metadata.history_size),
+        );
+
+        let sector_record_chunks = read_sector_record_chunks(
+            piece_offset,
+            pieces_in_sector,
+            &s_bucket_offsets,
+            &sector_contents_map,
+            &pos_table,
+            &sector_read,
+        )
+        .now_or_never()
+        .expect("Sync reader; qed")?;
+
+        let record_metadata =
+            read_record_metadata(piece_offset, pieces_in_sector, &sector_read)
+                .now_or_never()
+                .expect("Sync reader; qed")?;
+
+        // A `None` entry here only means the record has no chunk plotted in that s-bucket — a
+        // healthy record occupies just `Record::NUM_CHUNKS` of the `Record::NUM_S_BUCKETS`
+        // s-buckets — so the number of surviving shards, not its relation to `NUM_S_BUCKETS`, is
+        // what bounds recoverability.
+        let surviving = sector_record_chunks
+            .iter()
+            .filter(|chunk| chunk.is_some())
+            .count();
+
+        // Detect corruption by erasure-decoding the surviving chunks and re-deriving the record
+        // commitment; if it matches the metadata the record's contents are intact.
+        if record_commitment_matches(
+            kzg,
+            erasure_coding,
+            sector_record_chunks.as_slice(),
+            &record_metadata,
+        ) {
+            trace!(%piece_offset, "Record is healthy");
+            continue;
+        }
+
+        // Reaching here means a present shard is wrong, not merely missing (missing shards would
+        // have been filled by `recover_poly` above and the commitment would have matched). Locate
+        // the bad shard by turning it into an erasure and reconstruct from the remainder; this needs
+        // one spare shard beyond `Record::NUM_CHUNKS`, so a record storing exactly `NUM_CHUNKS`
+        // shards cannot be error-corrected.
+        let Some(recovered) = localize_and_recover(
+            kzg,
+            erasure_coding,
+            sector_record_chunks.as_slice(),
+            &record_metadata,
+        ) else {
+            warn!(
+                %piece_offset,
+                surviving,
+                "Corrupt record could not be localized or reconstructed"
+            );
+            report.unrecoverable.push(piece_offset);
+            continue;
+        };
+
+        debug!(%piece_offset, "Rewriting reconstructed record chunks");
+
+        // Hand the physical layout — s-bucket offsets and PoSpace masking — back to the canonical
+        // writer, the exact inverse of `read_sector_record_chunks`, so scrub never reimplements
+        // on-disk addressing and can only ever touch the s-buckets this record occupies.
+        write_sector_record_chunks(
+            &recovered,
+            piece_offset,
+            pieces_in_sector,
+            &s_bucket_offsets,
+            &sector_contents_map,
+            &pos_table,
+            sector,
+        )
+        .now_or_never()
+        .expect("Sync writer; qed")?;
+
+        // Re-read through the normal path and re-verify: only claim a repair once the rewrite has
+        // actually restored the commitment, otherwise the damage was beyond reconstruction.
+        let rechecked_chunks = read_sector_record_chunks(
+            piece_offset,
+            pieces_in_sector,
+            &s_bucket_offsets,
+            &sector_contents_map,
+            &pos_table,
+            &sector_read,
+        )
+        .now_or_never()
+        .expect("Sync reader; qed")?;
+
+        if record_commitment_matches(
+            kzg,
+            erasure_coding,
+            rechecked_chunks.as_slice(),
+            &record_metadata,
+        ) {
+            report.repaired.push(piece_offset);
+        } else {
+            warn!(%piece_offset, "Rewrite did not restore the record commitment");
+            report.unrecoverable.push(piece_offset);
+        }
+    }
+
+    Ok(report)
+}
+
+/// Locate a single present-but-wrong shard and reconstruct the record from the rest.
+///
+/// [`ErasureCoding::recover_poly`] treats `None` entries as erasures and trusts every `Some` entry,
+/// so a present but bit-flipped shard poisons the reconstruction rather than being corrected. To
+/// turn such an error into a correctable erasure we drop one surviving shard at a time and re-derive
+/// the commitment; the drop whose reconstruction matches the record metadata identifies the culprit,
+/// and the full recovered set is returned. This needs one spare shard beyond [`Record::NUM_CHUNKS`]
+/// — dropping a shard from a record that stores exactly `NUM_CHUNKS` leaves too few to reconstruct —
+/// so such records yield `None` and are reported unrecoverable.
+fn localize_and_recover(
+    kzg: &Kzg,
+    erasure_coding: &ErasureCoding,
+    sector_record_chunks: &[Option<Scalar>],
+    record_metadata: &RecordMetadata,
+) -> Option<Vec<Scalar>> {
+    for candidate in 0..sector_record_chunks.len() {
+        if sector_record_chunks[candidate].is_none() {
+            continue;
+        }
+
+        let mut trial = sector_record_chunks.to_vec();
+        trial[candidate] = None;
+        if record_commitment_matches(kzg, erasure_coding, &trial, record_metadata) {
+            return erasure_coding.recover(&trial).ok();
+        }
+    }
+
+    None
+}
+
+/// Re-derive the record commitment from the stored chunks and compare it against the commitment in
+/// the record metadata. Returns `false` if the chunks cannot be erasure-decoded or the commitment
+/// cannot be recomputed, both of which indicate corruption.
+fn record_commitment_matches(
+    kzg: &Kzg,
+    erasure_coding: &ErasureCoding,
+    sector_record_chunks: &[Option<Scalar>],
+    record_metadata: &RecordMetadata,
+) -> bool {
+    let Ok(polynomial) = erasure_coding.recover_poly(sector_record_chunks) else {
+        return false;
+    };
+
+    match kzg.commit(&polynomial) {
+        Ok(commitment) => RecordCommitment::from(commitment) == record_metadata.commitment,
+        Err(_error) => false,
+    }
+}
@@ -0,0 +1,249 @@
+//! At-rest encryption wrapper for [`PieceCache`].
+//!
+//! Following the pattern of server-side-encryption wrappers in object stores, [`EncryptedPieceCache`]
+//! transparently encrypts pieces on [`add_piece`](PieceCache::add_piece) and decrypts them on
+//! [`get_piece`](PieceCache::get_piece) so an operator storing cached pieces on shared or untrusted
+//! disks never exposes plaintext history. Encryption uses ChaCha20-Poly1305 with a per-piece nonce
+//! derived from the record key and a master key supplied at construction;
+//! [`should_cache`](PieceCache::should_cache) passes through unchanged.
+//!
+//! Because the underlying cache stores fixed-size [`Piece`]s, the AEAD is applied in detached mode:
+//! the length-preserving ciphertext replaces the piece contents in the wrapped cache, while the
+//! 16-byte authentication tag is kept in a sidecar persisted alongside. The nonce is re-derived
+//! deterministically from the record key, so the cache can be reopened with the correct master key
+//! across restarts.
+//!
+//! The tag sidecar is a single monolithic file shared by every piece rather than a tag per piece:
+//! one small file is cheaper to `fsync` and avoids a second inode per cached piece. To keep writes
+//! cheap it is an append-only log — adding a piece appends one `(key, tag)` record in O(1) instead
+//! of rewriting the whole map — and it is compacted (rewritten with only tags whose piece is still
+//! cached) once it holds at least `TAG_LOG_COMPACTION_FLOOR` records and superseded records (repeat
+//! appends for a key already logged) make up at least half of it. The trigger counts only
+//! superseded records: a tag whose piece the inner cache has evicted counts as live until a
+//! compaction runs, so evictions alone do not start one and the sidecar keeps one tag for every
+//! distinct piece added since the last compaction. The monolithic layout means a
+//! torn tail from a crash mid-append would otherwise poison the whole file, so replay stops at the
+//! first undecodable record; a piece whose tag is lost that way degrades on its own — it simply
+//! fails to decrypt and is refetched on the next miss — without affecting any other piece.
+
+use crate::commands::farm::dsn::PieceCache;
+use chacha20poly1305::aead::generic_array::GenericArray;
+use chacha20poly1305::{AeadInPlace, ChaCha20Poly1305, KeyInit};
+use parity_scale_codec::{Decode, Encode};
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use subspace_core_primitives::crypto::blake2b_256_hash;
+use subspace_core_primitives::Piece;
+use subspace_networking::libp2p::kad::record::Key;
+use tracing::warn;
+
+/// Length in bytes of the ChaCha20-Poly1305 nonce.
+const NONCE_LEN: usize = 12;
+/// Length in bytes of the Poly1305 authentication tag.
+const TAG_LEN: usize = 16;
+/// Minimum number of logged records before compaction is considered, so small caches do not rewrite
+/// the sidecar on every other insert.
+const TAG_LOG_COMPACTION_FLOOR: usize = 4096;
+
+/// One `(record key, tag)` record in the append-only tag log.
+#[derive(Debug, Encode, Decode)]
+struct TagRecord {
+    key: Vec<u8>,
+    tag: [u8; TAG_LEN],
+}
+
+/// Authentication tags for encrypted pieces, persisted so the cache can be decrypted after a
+/// restart. Backed by an append-only log on disk (keyed by the raw record key bytes) with an
+/// in-memory map for lookups; see the module docs for the on-disk format and compaction.
+#[derive(Debug)]
+struct TagStore {
+    tags: HashMap<Vec<u8>, [u8; TAG_LEN]>,
+    /// Records appended to the log, including superseded and now-dead ones; drives compaction.
+    log_len: usize,
+    path: PathBuf,
+}
+
+impl TagStore {
+    fn load(path: PathBuf) -> Self {
+        let mut tags = HashMap::new();
+        let mut log_len = 0;
+        if let Ok(bytes) = std::fs::read(&path) {
+            let mut input = bytes.as_slice();
+            // Replay the log record by record, stopping at the first undecodable one so a torn tail
+            // from a crash mid-append costs at most one piece rather than the whole sidecar.
+            while !input.is_empty() {
+                match TagRecord::decode(&mut input) {
+                    Ok(record) => {
+                        tags.insert(record.key, record.tag);
+                        log_len += 1;
+                    }
+                    Err(error) => {
+                        warn!(?error, "Stopping encrypted piece cache tag replay at corrupt record");
+                        break;
+                    }
+                }
+            }
+        }
+        Self {
+            tags,
+            log_len,
+            path,
+        }
+    }
+
+    /// Append a single `(key, tag)` record — an O(1) write — rather than re-serializing the map.
+    fn append(&mut self, key: &Key, tag: [u8; TAG_LEN]) {
+        let key = key.to_vec();
+        let record = TagRecord {
+            key: key.clone(),
+            tag,
+        };
+        match OpenOptions::new().create(true).append(true).open(&self.path) {
+            Ok(mut file) => {
+                if let Err(error) = file.write_all(&record.encode()) {
+                    warn!(?error, "Failed to append encrypted piece cache tag");
+                    return;
+                }
+                self.tags.insert(key, tag);
+                self.log_len += 1;
+            }
+            Err(error) => warn!(?error, "Failed to open encrypted piece cache tag log"),
+        }
+    }
+
+    fn get(&self, key: &Key) -> Option<&[u8; TAG_LEN]> {
+        self.tags.get(key.as_ref())
+    }
+
+    /// Rewrite the log with only tags whose piece is still cached, bounding the sidecar as pieces
+    /// are evicted. `is_live` reports whether a piece is still present in the inner cache.
+    fn compact(&mut self, is_live: impl Fn(&Key) -> bool) {
+        self.tags.retain(|key, _| is_live(&Key::from(key.clone())));
+
+        let mut buffer = Vec::new();
+        for (key, tag) in &self.tags {
+            TagRecord {
+                key: key.clone(),
+                tag: *tag,
+            }
+            .encode_to(&mut buffer);
+        }
+        if let Err(error) = std::fs::write(&self.path, &buffer) {
+            warn!(?error, "Failed to compact encrypted piece cache tag log");
+            return;
+        }
+        self.log_len = self.tags.len();
+    }
+}
+
+/// Wrapper that encrypts an inner [`PieceCache`]'s on-disk contents at rest.
+pub(super) struct EncryptedPieceCache<PC> {
+    inner: PC,
+    cipher: ChaCha20Poly1305,
+    tags: TagStore,
+}
+
+impl<PC> EncryptedPieceCache<PC> {
+    /// Wrap `inner` so its pieces are encrypted at rest with `master_key`. `tags_path` is where the
+    /// per-piece authentication tags are persisted across restarts.
+    pub(super) fn new(inner: PC, master_key: [u8; 32], tags_path: PathBuf) -> Self {
+        let cipher = ChaCha20Poly1305::new(GenericArray::from_slice(&master_key));
+        let tags = TagStore::load(tags_path);
+        Self {
+            inner,
+            cipher,
+            tags,
+        }
+    }
+
+    /// Derive the deterministic per-piece nonce from the record key, so a piece can be decrypted
+    /// without storing its nonce separately.
+    fn nonce_for(key: &Key) -> [u8; NONCE_LEN] {
+        let hash = blake2b_256_hash(key.as_ref());
+        let mut nonce = [0u8; NONCE_LEN];
+        nonce.copy_from_slice(&hash[..NONCE_LEN]);
+        nonce
+    }
+}
+
+impl<PC> EncryptedPieceCache<PC>
+where
+    PC: PieceCache,
+{
+    /// Compact the tag log once it holds at least `TAG_LOG_COMPACTION_FLOOR` records and superseded
+    /// records (more than one per key) make up at least half of it. Tags for pieces the inner cache
+    /// has since evicted are not counted by this trigger; they are dropped when a compaction runs.
+    fn maybe_compact(&mut self) {
+        if self.tags.log_len < TAG_LOG_COMPACTION_FLOOR
+            || self.tags.log_len < self.tags.tags.len() * 2
+        {
+            return;
+        }
+
+        let Self { inner, tags, .. } = self;
+        tags.compact(|key| inner.get_piece(key).is_some());
+    }
+}
+
+impl<PC> PieceCache for EncryptedPieceCache<PC>
+where
+    PC: PieceCache,
+{
+    fn should_cache(&self, key: &Key) -> bool {
+        self.inner.should_cache(key)
+    }
+
+    fn add_piece(&mut self, key: Key, piece: Piece) {
+        let nonce = Self::nonce_for(&key);
+        let mut buffer = piece.to_vec();
+        match self.cipher.encrypt_in_place_detached(
+            GenericArray::from_slice(&nonce),
+            &[],
+            &mut buffer,
+        ) {
+            Ok(tag) => {
+                let tag: [u8; TAG_LEN] = tag.into();
+                let ciphertext = match Piece::try_from(buffer.as_slice()) {
+                    Ok(ciphertext) => ciphertext,
+                    Err(error) => {
+                        warn!(?error, "Encrypted piece has unexpected length, dropping");
+                        return;
+                    }
+                };
+                self.tags.append(&key, tag);
+                self.inner.add_piece(key, ciphertext);
+                self.maybe_compact();
+            }
+            Err(error) => {
+                warn!(?error, "Failed to encrypt piece before caching, dropping");
+            }
+        }
+    }
+
+    fn get_piece(&self, key: &Key) -> Option<Piece> {
+        let ciphertext = self.inner.get_piece(key)?;
+        let Some(tag) = self.tags.get(key) else {
+            warn!("Missing authentication tag for cached piece, cannot decrypt");
+            return None;
+        };
+
+        let nonce = Self::nonce_for(key);
+        let mut buffer = ciphertext.to_vec();
+        match self.cipher.decrypt_in_place_detached(
+            GenericArray::from_slice(&nonce),
+            &[],
+            &mut buffer,
+            GenericArray::from_slice(tag),
+        ) {
+            Ok(()) => Piece::try_from(buffer.as_slice())
+                .map_err(|error| warn!(?error, "Decrypted piece has unexpected length"))
+                .ok(),
+            Err(_error) => {
+                warn!("Failed to authenticate cached piece, refusing to return plaintext");
+                None
+            }
+        }
+    }
+}
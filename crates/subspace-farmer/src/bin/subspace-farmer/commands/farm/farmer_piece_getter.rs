@@ -1,18 +1,123 @@
 use crate::commands::farm::dsn::PieceCache;
 use async_trait::async_trait;
+use prometheus_client::metrics::counter::Counter;
+use prometheus_client::metrics::histogram::{exponential_buckets, Histogram};
+use prometheus_client::registry::Registry;
 use std::error::Error;
 use std::sync::Arc;
-use subspace_core_primitives::{Piece, PieceIndex, PieceIndexHash};
+use std::time::Instant;
+use subspace_core_primitives::crypto::kzg::{Kzg, Scalar};
+use subspace_core_primitives::{Piece, PieceIndex, PieceIndexHash, RecordCommitment};
 use subspace_farmer_components::plotting::PieceGetter;
 use subspace_networking::utils::multihash::ToMultihash;
 use subspace_networking::utils::pieces::announce_single_piece_index_hash_with_backoff;
 use subspace_networking::Node;
-use tracing::debug;
+use thiserror::Error;
+use tracing::{debug, warn};
+
+/// Errors that happen while getting pieces through [`FarmerPieceGetter`].
+#[derive(Debug, Error)]
+pub(super) enum PieceGetterError {
+    /// Retrieved piece failed integrity verification
+    #[error("Retrieved piece for index {piece_index} failed integrity verification")]
+    IntegrityMismatch {
+        /// Piece index whose contents did not match its commitment
+        piece_index: PieceIndex,
+    },
+}
+
+/// Prometheus metrics for [`FarmerPieceGetter`], tracking where pieces come from and how
+/// expensive retrieval is.
+struct PieceGetterMetrics {
+    cache_hit: Counter,
+    cache_miss: Counter,
+    should_cache: Counter,
+    should_not_cache: Counter,
+    pieces_stored: Counter,
+    base_getter_seconds: Histogram,
+    announce_succeeded: Counter,
+    announce_failed: Counter,
+}
+
+impl PieceGetterMetrics {
+    fn new(registry: &mut Registry) -> Self {
+        let registry = registry.sub_registry_with_prefix("piece_getter");
+
+        let cache_hit = Counter::default();
+        registry.register(
+            "cache_hit",
+            "Pieces served directly from the local cache",
+            cache_hit.clone(),
+        );
+
+        let cache_miss = Counter::default();
+        registry.register(
+            "cache_miss",
+            "Pieces not found in the local cache and retrieved from the base getter",
+            cache_miss.clone(),
+        );
+
+        let should_cache = Counter::default();
+        registry.register(
+            "should_cache",
+            "Retrieved pieces the cache decided to keep",
+            should_cache.clone(),
+        );
+
+        let should_not_cache = Counter::default();
+        registry.register(
+            "should_not_cache",
+            "Retrieved pieces the cache decided not to keep",
+            should_not_cache.clone(),
+        );
+
+        let pieces_stored = Counter::default();
+        registry.register(
+            "pieces_stored",
+            "Pieces written into the local cache",
+            pieces_stored.clone(),
+        );
+
+        let base_getter_seconds = Histogram::new(exponential_buckets(0.001, 2.0, 15));
+        registry.register(
+            "base_getter_seconds",
+            "Base (DSN) piece getter retrieval latency in seconds",
+            base_getter_seconds.clone(),
+        );
+
+        let announce_succeeded = Counter::default();
+        registry.register(
+            "announce_succeeded",
+            "Successful piece index hash announcements after caching",
+            announce_succeeded.clone(),
+        );
+
+        let announce_failed = Counter::default();
+        registry.register(
+            "announce_failed",
+            "Failed piece index hash announcements after caching",
+            announce_failed.clone(),
+        );
+
+        Self {
+            cache_hit,
+            cache_miss,
+            should_cache,
+            should_not_cache,
+            pieces_stored,
+            base_getter_seconds,
+            announce_succeeded,
+            announce_failed,
+        }
+    }
+}
 
 pub(super) struct FarmerPieceGetter<PG, PC> {
     base_piece_getter: PG,
     piece_cache: Arc<tokio::sync::Mutex<PC>>,
     node: Node,
+    kzg: Kzg,
+    metrics: Option<PieceGetterMetrics>,
 }
 
 impl<PG, PC> FarmerPieceGetter<PG, PC> {
@@ -20,15 +125,58 @@ impl<PG, PC> FarmerPieceGetter<PG, PC> {
         base_piece_getter: PG,
         piece_cache: Arc<tokio::sync::Mutex<PC>>,
         node: Node,
+        kzg: Kzg,
+        registry: Option<&mut Registry>,
     ) -> Self {
         Self {
             base_piece_getter,
             piece_cache,
             node,
+            kzg,
+            metrics: registry.map(PieceGetterMetrics::new),
         }
     }
 }
 
+/// Reject malformed or corrupt records: recompute the record commitment of `piece` from its
+/// scalars using the same KZG machinery as the proving path and check it against the commitment
+/// carried by the piece, rejecting pieces whose record does not match its own commitment. This is
+/// analogous to an object store verifying a content checksum on write and catches garbled pieces
+/// before they are cached and re-announced to the DSN.
+///
+/// This is explicitly NOT a cache-poisoning defense: it is an internal self-consistency check that
+/// does not bind the piece to the requested [`PieceIndexHash`]/segment, so a self-consistent piece
+/// fabricated for a different index passes. Protection against substitution under a requested key
+/// depends entirely on the `base_piece_getter` being a validating getter — it verifies the record
+/// witness against the segment commitment for the piece's position before this getter ever sees the
+/// piece. If an unvalidated base getter is ever wired in, this check alone does not prevent
+/// poisoning.
+pub(super) fn verify_piece_integrity(kzg: &Kzg, piece: &Piece) -> bool {
+    let record_chunks = match piece
+        .record()
+        .iter()
+        .map(|chunk| Scalar::try_from(chunk))
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(record_chunks) => record_chunks,
+        Err(_error) => {
+            return false;
+        }
+    };
+
+    let polynomial = match kzg.poly(&record_chunks) {
+        Ok(polynomial) => polynomial,
+        Err(_error) => {
+            return false;
+        }
+    };
+
+    match kzg.commit(&polynomial) {
+        Ok(commitment) => RecordCommitment::from(commitment) == *piece.commitment(),
+        Err(_error) => false,
+    }
+}
+
 #[async_trait]
 impl<PG, PC> PieceGetter for FarmerPieceGetter<PG, PC>
 where
@@ -45,28 +193,63 @@ where
         let maybe_should_store = {
             let piece_cache = self.piece_cache.lock().await;
             if let Some(piece) = piece_cache.get_piece(&key) {
+                if let Some(metrics) = &self.metrics {
+                    metrics.cache_hit.inc();
+                }
                 return Ok(Some(piece));
             }
 
             piece_cache.should_cache(&key)
         };
 
+        if let Some(metrics) = &self.metrics {
+            metrics.cache_miss.inc();
+            if maybe_should_store {
+                metrics.should_cache.inc();
+            } else {
+                metrics.should_not_cache.inc();
+            }
+        }
+
+        let start = Instant::now();
         let maybe_piece = self.base_piece_getter.get_piece(piece_index).await?;
+        if let Some(metrics) = &self.metrics {
+            metrics
+                .base_getter_seconds
+                .observe(start.elapsed().as_secs_f64());
+        }
 
         if let Some(piece) = &maybe_piece {
+            if !verify_piece_integrity(&self.kzg, piece) {
+                warn!(
+                    %piece_index,
+                    "Retrieved piece has a malformed or corrupt record, refusing to cache or \
+                    announce"
+                );
+                return Err(Box::new(PieceGetterError::IntegrityMismatch { piece_index }));
+            }
+
             if maybe_should_store {
                 let mut piece_cache = self.piece_cache.lock().await;
                 if piece_cache.should_cache(&key) && piece_cache.get_piece(&key).is_none() {
                     piece_cache.add_piece(key, piece.clone());
+                    if let Some(metrics) = &self.metrics {
+                        metrics.pieces_stored.inc();
+                    }
                     if let Err(error) =
                         announce_single_piece_index_hash_with_backoff(piece_index_hash, &self.node)
                             .await
                     {
+                        if let Some(metrics) = &self.metrics {
+                            metrics.announce_failed.inc();
+                        }
                         debug!(
                             ?error,
                             ?piece_index_hash,
                             "Announcing retrieved and cached piece index hash failed"
                         );
+                    } else if let Some(metrics) = &self.metrics {
+                        metrics.announce_succeeded.inc();
                     }
                 }
             }
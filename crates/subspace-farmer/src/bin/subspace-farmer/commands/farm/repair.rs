@@ -0,0 +1,389 @@
+//! Background resync/repair worker that keeps cached pieces healthy and discoverable.
+//!
+//! Modeled on the resync queues used by distributed block stores: a persistent priority queue
+//! keyed by "next check time" drives a bounded pool of workers that verify each stored piece,
+//! re-fetch any that fail verification or were evicted while still satisfying [`should_cache`],
+//! and re-announce piece index hashes whose DSN provider records are approaching expiry. A rate
+//! limiter keeps repair from starving live [`FarmerPieceGetter::get_piece`] traffic, and failed
+//! announcements are retried with exponential backoff so a self-healing cache stays on the
+//! network instead of silently rotting between restarts.
+//!
+//! [`should_cache`]: PieceCache::should_cache
+//! [`FarmerPieceGetter::get_piece`]: super::farmer_piece_getter::FarmerPieceGetter
+
+use crate::commands::farm::dsn::PieceCache;
+use crate::commands::farm::farmer_piece_getter::verify_piece_integrity;
+use parity_scale_codec::{Decode, Encode};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use subspace_core_primitives::crypto::kzg::Kzg;
+use subspace_core_primitives::{PieceIndex, PieceIndexHash};
+use subspace_farmer_components::plotting::PieceGetter;
+use subspace_networking::utils::multihash::ToMultihash;
+use subspace_networking::utils::pieces::announce_single_piece_index_hash_with_backoff;
+use subspace_networking::Node;
+use tokio::sync::{mpsc, Mutex, Semaphore};
+use tokio::time::sleep;
+use tracing::{debug, trace, warn};
+
+/// Current wall-clock time in milliseconds since the Unix epoch. Wall-clock timestamps are used
+/// (rather than `Instant`-relative offsets) so the persisted schedule stays meaningful across
+/// restarts.
+fn now_unix_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_millis() as u64
+}
+
+/// Tunables controlling how aggressively the repair worker runs.
+#[derive(Debug, Clone)]
+pub(super) struct RepairConfig {
+    /// How often a healthy piece is re-checked
+    pub(super) check_interval: Duration,
+    /// Provider records younger than this are considered fresh and are not re-announced
+    pub(super) announce_ttl: Duration,
+    /// Upper bound on concurrent re-fetches
+    pub(super) max_concurrent_refetches: usize,
+    /// Minimum delay between two repair actions, so live traffic is not starved
+    pub(super) rate_limit: Duration,
+    /// Upper bound on the exponential backoff applied after repeated announce failures
+    pub(super) max_backoff: Duration,
+    /// Where the priority queue is persisted across restarts
+    pub(super) state_path: PathBuf,
+}
+
+impl Default for RepairConfig {
+    fn default() -> Self {
+        Self {
+            check_interval: Duration::from_secs(60 * 60),
+            announce_ttl: Duration::from_secs(22 * 60 * 60),
+            max_concurrent_refetches: 4,
+            rate_limit: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(60 * 60),
+            state_path: PathBuf::from("piece-cache-repair.scale"),
+        }
+    }
+}
+
+/// A piece tracked by the repair worker, persisted across restarts.
+#[derive(Debug, Clone, Encode, Decode)]
+struct TrackedPiece {
+    piece_index: PieceIndex,
+    /// Number of consecutive announce failures, driving exponential backoff
+    announce_failures: u32,
+    /// Wall-clock time (ms since Unix epoch) of the next scheduled check
+    next_check_unix_ms: u64,
+    /// Wall-clock time (ms since Unix epoch) of the last successful announcement, used to skip
+    /// re-announcing provider records that are still fresh. `None` until the piece has been
+    /// announced once.
+    last_announce_unix_ms: Option<u64>,
+}
+
+impl PartialEq for TrackedPiece {
+    fn eq(&self, other: &Self) -> bool {
+        self.next_check_unix_ms == other.next_check_unix_ms
+    }
+}
+
+impl Eq for TrackedPiece {}
+
+impl PartialOrd for TrackedPiece {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TrackedPiece {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.next_check_unix_ms.cmp(&other.next_check_unix_ms)
+    }
+}
+
+/// Persistent priority queue keyed by next check time. The soonest-due piece is always popped
+/// first (via [`Reverse`] in the heap), matching the scheduling of a resync queue.
+#[derive(Debug, Default, Encode, Decode)]
+struct RepairQueue {
+    // Stored as a plain vec for persistence; rebuilt into a heap in memory.
+    entries: Vec<TrackedPiece>,
+}
+
+impl RepairQueue {
+    fn load(state_path: &PathBuf) -> Self {
+        match std::fs::read(state_path) {
+            Ok(bytes) => Self::decode(&mut bytes.as_slice()).unwrap_or_else(|error| {
+                warn!(?error, "Failed to decode repair queue, starting empty");
+                Self::default()
+            }),
+            Err(_error) => Self::default(),
+        }
+    }
+
+    fn persist(&self, state_path: &PathBuf) {
+        if let Err(error) = std::fs::write(state_path, self.encode()) {
+            warn!(?error, "Failed to persist repair queue");
+        }
+    }
+
+    fn into_heap(self) -> BinaryHeap<Reverse<TrackedPiece>> {
+        self.entries.into_iter().map(Reverse).collect()
+    }
+
+    fn from_heap(heap: &BinaryHeap<Reverse<TrackedPiece>>) -> Self {
+        Self {
+            entries: heap.iter().map(|Reverse(piece)| piece.clone()).collect(),
+        }
+    }
+}
+
+/// Background repair worker keeping a [`PieceCache`] healthy and announced.
+pub(super) struct RepairWorker<PG, PC> {
+    base_piece_getter: PG,
+    piece_cache: Arc<Mutex<PC>>,
+    node: Node,
+    kzg: Kzg,
+    config: RepairConfig,
+}
+
+impl<PG, PC> RepairWorker<PG, PC>
+where
+    PG: PieceGetter + Send + Sync + 'static,
+    PC: PieceCache + Send + 'static,
+{
+    pub(super) fn new(
+        base_piece_getter: PG,
+        piece_cache: Arc<Mutex<PC>>,
+        node: Node,
+        kzg: Kzg,
+        config: RepairConfig,
+    ) -> Self {
+        Self {
+            base_piece_getter,
+            piece_cache,
+            node,
+            kzg,
+            config,
+        }
+    }
+
+    /// Run the repair loop until the task is dropped. The shared priority queue is drained in
+    /// due-time order; each due piece is handed to a spawned worker that holds one of
+    /// [`RepairConfig::max_concurrent_refetches`] permits for its lifetime, so independent pieces
+    /// are repaired concurrently while the pool stays bounded. `new_pieces` feeds piece indices
+    /// cached after startup (e.g. by [`FarmerPieceGetter::get_piece`]) into the same schedule so the
+    /// worker keeps tracking freshly cached pieces instead of only the `initial` set. The rate
+    /// limiter spaces out spawns to leave headroom for live `get_piece` traffic.
+    ///
+    /// [`FarmerPieceGetter::get_piece`]: super::farmer_piece_getter::FarmerPieceGetter
+    pub(super) async fn run(
+        self: Arc<Self>,
+        initial: impl IntoIterator<Item = PieceIndex>,
+        mut new_pieces: mpsc::UnboundedReceiver<PieceIndex>,
+    ) {
+        let queue = Arc::new(Mutex::new(
+            RepairQueue::load(&self.config.state_path).into_heap(),
+        ));
+        {
+            let mut queue = queue.lock().await;
+            for piece_index in initial {
+                // Newly tracked pieces are due immediately.
+                queue.push(Reverse(TrackedPiece {
+                    piece_index,
+                    announce_failures: 0,
+                    next_check_unix_ms: now_unix_ms(),
+                    last_announce_unix_ms: None,
+                }));
+            }
+        }
+
+        let refetch_slots = Arc::new(Semaphore::new(self.config.max_concurrent_refetches));
+        let mut new_pieces_open = true;
+
+        loop {
+            // How long until the soonest-due piece; `check_interval` when nothing is tracked so we
+            // never busy-spin while waiting for the first piece to arrive.
+            let wait = {
+                let queue = queue.lock().await;
+                match queue.peek() {
+                    Some(Reverse(next)) => Duration::from_millis(
+                        next.next_check_unix_ms.saturating_sub(now_unix_ms()),
+                    ),
+                    None => self.config.check_interval,
+                }
+            };
+
+            // Wait until the next piece is due, but wake early to track a newly cached piece.
+            if new_pieces_open {
+                tokio::select! {
+                    maybe_index = new_pieces.recv() => {
+                        match maybe_index {
+                            Some(piece_index) => {
+                                queue.lock().await.push(Reverse(TrackedPiece {
+                                    piece_index,
+                                    announce_failures: 0,
+                                    next_check_unix_ms: now_unix_ms(),
+                                    last_announce_unix_ms: None,
+                                }));
+                            }
+                            // Producer dropped; keep repairing what we already track.
+                            None => new_pieces_open = false,
+                        }
+                        continue;
+                    }
+                    _ = sleep(wait) => {}
+                }
+            } else {
+                sleep(wait).await;
+            }
+
+            // Pop the piece only if it is actually due now — a newly cached (due-immediately) piece
+            // may have jumped ahead of whatever we were waiting on.
+            let tracked = {
+                let mut queue = queue.lock().await;
+                match queue.peek() {
+                    Some(Reverse(next)) if next.next_check_unix_ms <= now_unix_ms() => {
+                        let Reverse(tracked) = queue.pop().expect("Just peeked; qed");
+                        tracked
+                    }
+                    _ => continue,
+                }
+            };
+
+            // Acquire a slot before spawning so the worker pool stays bounded; the permit is held
+            // for the whole repair and released when the spawned task finishes.
+            let permit = refetch_slots
+                .clone()
+                .acquire_owned()
+                .await
+                .expect("Semaphore is never closed; qed");
+
+            let worker = Arc::clone(&self);
+            let queue = Arc::clone(&queue);
+            tokio::spawn(async move {
+                let outcome = worker
+                    .repair_piece(
+                        tracked.piece_index,
+                        tracked.announce_failures,
+                        tracked.last_announce_unix_ms,
+                    )
+                    .await;
+
+                let backoff = worker.backoff_for(outcome.announce_failures);
+                let delay = if outcome.announce_failures == 0 {
+                    worker.config.check_interval
+                } else {
+                    backoff
+                };
+
+                let mut queue = queue.lock().await;
+                queue.push(Reverse(TrackedPiece {
+                    piece_index: tracked.piece_index,
+                    announce_failures: outcome.announce_failures,
+                    next_check_unix_ms: now_unix_ms() + delay.as_millis() as u64,
+                    last_announce_unix_ms: outcome.last_announce_unix_ms,
+                }));
+                RepairQueue::from_heap(&queue).persist(&worker.config.state_path);
+
+                // Release the slot only once the piece is rescheduled and persisted.
+                drop(permit);
+            });
+
+            // Rate limit so repair never starves live retrieval traffic.
+            sleep(self.config.rate_limit).await;
+        }
+    }
+
+    /// Exponential backoff bounded by [`RepairConfig::max_backoff`].
+    fn backoff_for(&self, announce_failures: u32) -> Duration {
+        if announce_failures == 0 {
+            return Duration::ZERO;
+        }
+        let shift = announce_failures.min(16);
+        self.config
+            .rate_limit
+            .saturating_mul(1u32 << shift)
+            .min(self.config.max_backoff)
+    }
+
+    /// Verify, re-fetch if necessary, and re-announce a single tracked piece. Announcing is
+    /// skipped while the previous announcement is still within [`RepairConfig::announce_ttl`], so
+    /// provider records are refreshed only as they approach expiry.
+    async fn repair_piece(
+        &self,
+        piece_index: PieceIndex,
+        announce_failures: u32,
+        last_announce_unix_ms: Option<u64>,
+    ) -> RepairOutcome {
+        let piece_index_hash = PieceIndexHash::from_index(piece_index);
+        let key = piece_index_hash.to_multihash().into();
+
+        let (cached, should_cache) = {
+            let piece_cache = self.piece_cache.lock().await;
+            (piece_cache.get_piece(&key), piece_cache.should_cache(&key))
+        };
+
+        let healthy = cached
+            .as_ref()
+            .is_some_and(|piece| verify_piece_integrity(&self.kzg, piece));
+
+        if !healthy && should_cache {
+            trace!(%piece_index, "Cached piece missing or corrupt, re-fetching");
+            match self.base_piece_getter.get_piece(piece_index).await {
+                Ok(Some(piece)) if verify_piece_integrity(&self.kzg, &piece) => {
+                    let mut piece_cache = self.piece_cache.lock().await;
+                    if piece_cache.should_cache(&key) {
+                        piece_cache.add_piece(key.clone(), piece);
+                    }
+                }
+                Ok(_) => {
+                    warn!(%piece_index, "Re-fetched piece unavailable or failed verification");
+                }
+                Err(error) => {
+                    warn!(%piece_index, ?error, "Failed to re-fetch piece during repair");
+                }
+            }
+        }
+
+        // Only re-announce when the existing provider record is approaching expiry.
+        let announce_fresh = last_announce_unix_ms.is_some_and(|last| {
+            now_unix_ms().saturating_sub(last) < self.config.announce_ttl.as_millis() as u64
+        });
+        if announce_fresh {
+            trace!(%piece_index, "Provider record still fresh, skipping re-announcement");
+            return RepairOutcome {
+                announce_failures: 0,
+                last_announce_unix_ms,
+            };
+        }
+
+        // Re-announce so provider records stay fresh well before they expire.
+        match announce_single_piece_index_hash_with_backoff(piece_index_hash, &self.node).await {
+            Ok(()) => {
+                debug!(%piece_index, "Re-announced cached piece during repair");
+                RepairOutcome {
+                    announce_failures: 0,
+                    last_announce_unix_ms: Some(now_unix_ms()),
+                }
+            }
+            Err(error) => {
+                warn!(%piece_index, ?error, "Failed to re-announce piece during repair");
+                RepairOutcome {
+                    announce_failures: announce_failures.saturating_add(1),
+                    last_announce_unix_ms,
+                }
+            }
+        }
+    }
+}
+
+/// Result of repairing a single piece, fed back into the priority queue for scheduling.
+struct RepairOutcome {
+    /// Consecutive announce-failure count driving exponential backoff
+    announce_failures: u32,
+    /// Wall-clock time of the last successful announcement, carried forward if this check did not
+    /// announce
+    last_announce_unix_ms: Option<u64>,
+}